@@ -1,7 +1,17 @@
 use std::iter;
 
+// How a negative value's remainder is reconciled against the (always non-negative)
+// target_remainder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ModConvention {
+    // Non-negative result in 0..divisor, e.g. (-1) mod 3 == 2
+    Euclidean,
+    // Sign follows the dividend, matching Rust's `%`, e.g. (-1) % 3 == -1
+    Truncated,
+}
+
 // Enum to represent regex types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Regex {
     Empty,              // Matches nothing
     Epsilon,            // Matches the empty string
@@ -12,17 +22,23 @@ pub enum Regex {
     Not(Box<Regex>),    // Negation
     Star(Box<Regex>),   // Kleene star
     Remainder {
-        // Value mod divisor is target_remainder
-        divisor: u32,
-        current_remainder: u32,
-        target_remainder: u32,
+        // Value mod divisor is target_remainder, both already scaled by radix^scale
+        radix: u32,
+        divisor: u128,
+        current_remainder: u128,
+        target_remainder: u128,
         scale: u32,
         fractional_mode: bool,
+        // Whether any character (sign, point or digit) has been consumed, after
+        // which a sign is no longer accepted, and whether a leading '-' was read.
+        seen_digit: bool,
+        negative: bool,
+        convention: ModConvention,
     },
 }
 
 impl Regex {
-    fn nullable(&self) -> bool {
+    pub(crate) fn nullable(&self) -> bool {
         match self {
             Regex::Empty => false,
             Regex::Epsilon => true,
@@ -33,10 +49,19 @@ impl Regex {
             Regex::Not(r) => !r.nullable(),
             Regex::Star(_) => true,
             Regex::Remainder {
+                divisor,
                 current_remainder,
                 target_remainder,
+                negative,
+                convention,
                 ..
-            } => current_remainder == target_remainder,
+            } => {
+                if *negative && *convention == ModConvention::Euclidean {
+                    (divisor - current_remainder) % divisor == *target_remainder
+                } else {
+                    current_remainder == target_remainder
+                }
+            }
         }
     }
 
@@ -48,7 +73,7 @@ impl Regex {
         }
     }
 
-    fn derivative(&self, c: &char) -> Regex {
+    pub(crate) fn derivative(&self, c: &char) -> Regex {
         match self {
             Regex::Empty => Regex::Empty,
             Regex::Epsilon => Regex::Empty,
@@ -80,36 +105,62 @@ impl Regex {
             Regex::Not(r) => Regex::Not(Box::new(r.derivative(c))),
             Regex::Star(r) => Regex::Concat(vec![r.derivative(c), Regex::Star(r.clone())]),
             Regex::Remainder {
+                radix,
                 divisor,
                 current_remainder,
                 target_remainder,
                 scale,
                 fractional_mode,
+                seen_digit,
+                negative,
+                convention,
             } => {
-                if !fractional_mode && c == &'.' {
+                if !seen_digit && (c == &'-' || c == &'+') {
                     Regex::Remainder {
+                        radix: *radix,
+                        divisor: *divisor,
+                        current_remainder: *current_remainder,
+                        target_remainder: *target_remainder,
+                        scale: *scale,
+                        fractional_mode: *fractional_mode,
+                        seen_digit: true,
+                        negative: c == &'-',
+                        convention: *convention,
+                    }
+                } else if !fractional_mode && c == &'.' {
+                    Regex::Remainder {
+                        radix: *radix,
                         divisor: *divisor,
                         current_remainder: *current_remainder,
                         target_remainder: *target_remainder,
                         scale: *scale,
                         fractional_mode: true,
+                        seen_digit: true,
+                        negative: *negative,
+                        convention: *convention,
                     }
-                } else if let Some(digit) = c.to_digit(10) {
+                } else if let Some(digit) = c.to_digit(*radix) {
                     if *fractional_mode && *scale == 0 {
                         return Regex::Empty;
                     }
+                    let digit = digit as u128;
+                    let radix = *radix as u128;
                     let current_remainder = if !fractional_mode {
-                        (current_remainder * 10 + digit * 10_u32.pow(*scale)) % divisor
+                        (current_remainder * radix + digit * radix.pow(*scale)) % divisor
                     } else {
-                        (current_remainder + digit * 10_u32.pow(*scale - 1)) % divisor
+                        (current_remainder + digit * radix.pow(*scale - 1)) % divisor
                     };
                     let scale = if *fractional_mode { *scale - 1 } else { *scale };
                     Regex::Remainder {
+                        radix: radix as u32,
                         divisor: *divisor,
                         current_remainder,
                         target_remainder: *target_remainder,
                         scale: scale,
                         fractional_mode: *fractional_mode,
+                        seen_digit: true,
+                        negative: *negative,
+                        convention: *convention,
                     }
                 } else {
                     Regex::Empty
@@ -126,6 +177,13 @@ impl Regex {
         current.nullable()
     }
 
+    // Rewrites `s` into the plain fixed-point form `matches` understands (see
+    // `crate::normalize`) before matching against it, so scientific notation and
+    // grouping separators are handled without changing `matches` itself.
+    pub fn matches_normalized(&self, s: &str, options: &crate::normalize::NormalizeOptions) -> bool {
+        self.matches(&crate::normalize::normalize(s, options))
+    }
+
     // Highly suboptimal implementation of the repeat operator
     pub fn repeat(r: Regex, low: u32, high: Option<u32>) -> Regex {
         let mut result = vec![];
@@ -154,28 +212,125 @@ impl Regex {
         let (divisor, scale) = scale_divisor(divisor)?;
 
         Ok(Regex::Remainder {
+            radix: 10,
             divisor,
             current_remainder: 0,
-            target_remainder: remainder,
+            target_remainder: remainder as u128,
             scale: scale,
             fractional_mode: false,
+            seen_digit: false,
+            negative: false,
+            convention: ModConvention::Euclidean,
         })
     }
 
     pub fn remainder(divisor: u32, remainder: u32) -> Regex {
         Regex::Remainder {
-            divisor,
+            radix: 10,
+            divisor: divisor as u128,
             current_remainder: 0,
-            target_remainder: remainder,
+            target_remainder: remainder as u128,
             scale: 0,
             fractional_mode: false,
+            seen_digit: false,
+            negative: false,
+            convention: ModConvention::Euclidean,
         }
     }
+
+    // Parses the divisor and the target exactly, so e.g. "0.25" is never rounded
+    // through a float. The target can itself be a decimal: both are scaled up to
+    // whichever has more fractional digits before comparing, so "prices whose
+    // remainder mod 0.25 is 0.05" is a single Remainder.
+    pub fn remainder_str(divisor: &str, remainder: &str) -> Result<Regex, String> {
+        Self::remainder_radix(10, divisor, remainder)
+    }
+
+    // Same as `remainder_str`, but for numerals in an arbitrary radix (2..=36) -
+    // useful for hexadecimal, octal, or binary numerals. The radix point stays '.'.
+    pub fn remainder_radix(radix: u32, divisor: &str, remainder: &str) -> Result<Regex, String> {
+        Self::remainder_radix_signed(radix, divisor, remainder, ModConvention::Euclidean)
+    }
+
+    // Same as `remainder_radix` (which uses `ModConvention::Euclidean`), but
+    // reconciles a negative value's remainder against the target using `convention`.
+    // Every constructor accepts an optional leading '-'/'+' sign.
+    pub fn remainder_radix_signed(
+        radix: u32,
+        divisor: &str,
+        remainder: &str,
+        convention: ModConvention,
+    ) -> Result<Regex, String> {
+        if !(2..=36).contains(&radix) {
+            return Err(format!("Radix must be between 2 and 36, got {}", radix));
+        }
+
+        let (divisor, divisor_scale) = parse_scaled_number(divisor, radix)?;
+        let (remainder, remainder_scale) = parse_scaled_number(remainder, radix)?;
+
+        if divisor == 0 {
+            return Err("Divisor must be non-zero".to_string());
+        }
+
+        let scale = divisor_scale.max(remainder_scale);
+        let radix128 = radix as u128;
+        let divisor_factor = radix128
+            .checked_pow(scale - divisor_scale)
+            .ok_or_else(|| format!("Divisor scale {} is too large for radix {}", scale, radix))?;
+        let remainder_factor = radix128
+            .checked_pow(scale - remainder_scale)
+            .ok_or_else(|| {
+                format!("Remainder scale {} is too large for radix {}", scale, radix)
+            })?;
+        let divisor = divisor
+            .checked_mul(divisor_factor)
+            .ok_or_else(|| "Scaled divisor exceeds u128::MAX".to_string())?;
+        let remainder = remainder
+            .checked_mul(remainder_factor)
+            .ok_or_else(|| "Scaled remainder exceeds u128::MAX".to_string())?;
+
+        Ok(Regex::Remainder {
+            radix,
+            divisor,
+            current_remainder: 0,
+            target_remainder: remainder,
+            scale,
+            fractional_mode: false,
+            seen_digit: false,
+            negative: false,
+            convention,
+        })
+    }
 }
 
-fn scale_divisor(divisor: f32) -> Result<(u32, u32), String> {
+// Splits a numeral on the radix point and concatenates the digits into a scaled
+// integer, without ever constructing a float.
+fn parse_scaled_number(s: &str, radix: u32) -> Result<(u128, u32), String> {
+    let mut parts = s.splitn(2, '.');
+    let integer_part = parts.next().unwrap_or("");
+    let fractional_part = parts.next().unwrap_or("");
+
+    if integer_part.is_empty() && fractional_part.is_empty() {
+        return Err(format!("Invalid number: {:?}", s));
+    }
+
+    let mut value: u128 = 0;
+    for c in integer_part.chars().chain(fractional_part.chars()) {
+        let digit = c
+            .to_digit(radix)
+            .ok_or_else(|| format!("Invalid digit {:?} for radix {}", c, radix))?;
+        value = value
+            .checked_mul(radix as u128)
+            .and_then(|v| v.checked_add(digit as u128))
+            .ok_or_else(|| format!("Value out of range: {:?}", s))?;
+    }
+
+    Ok((value, fractional_part.len() as u32))
+}
+
+fn scale_divisor(divisor: f32) -> Result<(u128, u32), String> {
     if divisor.fract() == 0.0 {
-        Ok((divisor.abs() as u32, 0))
+        Ok((divisor.abs() as u128, 0))
     } else {
         let divisor_str = divisor.to_string();
         let decimal_part = divisor_str
@@ -184,11 +339,11 @@ fn scale_divisor(divisor: f32) -> Result<(u32, u32), String> {
             .ok_or("No decimal part found")?;
         let scale = decimal_part.len();
         let scaled_divisor = divisor * 10_f32.powi(scale as i32);
-        if scaled_divisor > u32::MAX as f32 {
-            return Err("Scaled divisor exceeds u32::MAX".to_string());
+        if scaled_divisor > u128::MAX as f32 {
+            return Err("Scaled divisor exceeds u128::MAX".to_string());
         }
 
-        Ok((scaled_divisor.abs() as u32, scale as u32))
+        Ok((scaled_divisor.abs() as u128, scale as u32))
     }
 }
 
@@ -274,4 +429,103 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_remainder_str_exact() {
+        // Unlike `fractional_remainder`, this never round-trips through a float, so
+        // every tenth from 0.0 to 9.9 can be checked for exact divisibility by 2.5.
+        let regex = Regex::remainder_str("2.5", "0").unwrap();
+        for tenths in 0..100 {
+            let s = format!("{}.{}", tenths / 10, tenths % 10);
+            assert_eq!(
+                regex.matches(&s),
+                tenths % 25 == 0,
+                "{:?} ({} % 2.5 == 0)",
+                regex,
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_remainder_str_fractional_target() {
+        // "prices that end in .25 or .75" - remainder mod 0.5 is 0.25
+        let regex = Regex::remainder_str("0.5", "0.25").unwrap();
+        assert_eq!(regex.matches("1.25"), true);
+        assert_eq!(regex.matches("1.75"), true);
+        assert_eq!(regex.matches("1.5"), false);
+        assert_eq!(regex.matches("1.0"), false);
+    }
+
+    #[test]
+    fn test_remainder_radix_hex() {
+        // 0x10 (16) is divisible by 0x8 (8)
+        let regex = Regex::remainder_radix(16, "8", "0").unwrap();
+        assert_eq!(regex.matches("10"), true);
+        assert_eq!(regex.matches("ff"), false);
+        assert_eq!(regex.matches("f8"), true);
+    }
+
+    #[test]
+    fn test_remainder_radix_binary_fraction() {
+        // 0b1.1 (1.5) mod 0b0.1 (0.5) is 0
+        let regex = Regex::remainder_radix(2, "0.1", "0").unwrap();
+        assert_eq!(regex.matches("1.1"), true);
+        assert_eq!(regex.matches("1.01"), false);
+    }
+
+    #[test]
+    fn test_remainder_signed_euclidean() {
+        // (-1) mod 3 == 2 (Euclidean), so this should match -1, -4, -7, ...
+        let regex =
+            Regex::remainder_radix_signed(10, "3", "2", ModConvention::Euclidean).unwrap();
+        assert_eq!(regex.matches("-1"), true);
+        assert_eq!(regex.matches("-4"), true);
+        assert_eq!(regex.matches("2"), true);
+        assert_eq!(regex.matches("-2"), false);
+        assert_eq!(regex.matches("+2"), true);
+    }
+
+    #[test]
+    fn test_remainder_signed_truncated() {
+        // Rust's `%`: (-1) % 3 == -1, so the magnitude alone decides the match,
+        // regardless of sign.
+        let regex =
+            Regex::remainder_radix_signed(10, "3", "1", ModConvention::Truncated).unwrap();
+        assert_eq!(regex.matches("-1"), true);
+        assert_eq!(regex.matches("1"), true);
+        assert_eq!(regex.matches("-4"), true);
+        assert_eq!(regex.matches("-2"), false);
+    }
+
+    #[test]
+    fn test_remainder_str_wide_divisor() {
+        // A divisor near u32::MAX would overflow the old u32 arithmetic; u128
+        // intermediates keep this exact.
+        let regex = Regex::remainder_str("4294967290", "0").unwrap();
+        assert_eq!(regex.matches("4294967290"), true);
+        assert_eq!(regex.matches("8589934580"), true);
+        assert_eq!(regex.matches("4294967291"), false);
+    }
+
+    #[test]
+    fn test_remainder_str_deep_scale_does_not_panic() {
+        // A scale difference this large would overflow u128::pow before this guard;
+        // it must return an Err instead of panicking.
+        let divisor = format!("0.{}1", "0".repeat(40));
+        assert!(Regex::remainder_str(&divisor, "0").is_err());
+    }
+
+    #[test]
+    fn test_remainder_str_long_integer_part_does_not_panic() {
+        // Enough digits to overflow u128 while accumulating the divisor itself,
+        // before any scale-alignment step runs; must return an Err, not panic.
+        let divisor = "9".repeat(40);
+        assert!(Regex::remainder_str(&divisor, "0").is_err());
+    }
+
+    #[test]
+    fn test_remainder_str_rejects_zero_divisor() {
+        assert!(Regex::remainder_str("0", "0").is_err());
+    }
 }
@@ -0,0 +1,281 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::ast::Regex;
+
+// Brzozowski's construction: a state is a derived Regex, canonicalized so that
+// structurally-equivalent derivatives collapse onto the same state and the state
+// set stays finite. Built once from a Regex, then matching is a plain O(n) table
+// walk with no allocation, and the same Dfa can be reused across many inputs.
+//
+// `derivative` only ever branches on a character via an exact `Literal` equality
+// check or, inside `Remainder`, via `c.to_digit(radix)` / `c == '.'|'+'|'-'`. So any
+// character that is neither a literal appearing in the tree nor one of those digit
+// forms is indistinguishable from any other such character - they all take the
+// same transition. `collect_alphabet` gathers the distinguished characters; the
+// last column of the transition table is the shared "everything else" bucket,
+// computed from one representative character outside that set.
+pub struct Dfa {
+    alphabet: Vec<char>,
+    transitions: Vec<Vec<usize>>,
+    accepting: Vec<bool>,
+}
+
+impl Dfa {
+    pub fn from_regex(regex: &Regex) -> Dfa {
+        let mut alphabet_set = BTreeSet::new();
+        collect_alphabet(regex, &mut alphabet_set);
+        let wildcard = pick_wildcard(&alphabet_set);
+        let mut alphabet: Vec<char> = alphabet_set.into_iter().collect();
+        alphabet.push(wildcard);
+
+        let mut state_index: HashMap<Regex, usize> = HashMap::new();
+        let mut states: Vec<Regex> = Vec::new();
+        let mut transitions: Vec<Vec<usize>> = Vec::new();
+        let mut worklist: VecDeque<usize> = VecDeque::new();
+
+        let start = canonicalize(regex);
+        state_index.insert(start.clone(), 0);
+        states.push(start);
+        transitions.push(Vec::new());
+        worklist.push_back(0);
+
+        while let Some(state_id) = worklist.pop_front() {
+            let mut row = Vec::with_capacity(alphabet.len());
+            for c in &alphabet {
+                let next = canonicalize(&states[state_id].derivative(c));
+                let next_id = match state_index.get(&next) {
+                    Some(&id) => id,
+                    None => {
+                        let id = states.len();
+                        state_index.insert(next.clone(), id);
+                        states.push(next);
+                        transitions.push(Vec::new());
+                        worklist.push_back(id);
+                        id
+                    }
+                };
+                row.push(next_id);
+            }
+            transitions[state_id] = row;
+        }
+
+        let accepting = states.iter().map(|r| r.nullable()).collect();
+
+        Dfa {
+            alphabet,
+            transitions,
+            accepting,
+        }
+    }
+
+    pub fn matches(&self, s: &str) -> bool {
+        let wildcard_idx = self.alphabet.len() - 1;
+        let mut state = 0usize;
+        for c in s.chars() {
+            let idx = self.alphabet[..wildcard_idx]
+                .iter()
+                .position(|a| a == &c)
+                .unwrap_or(wildcard_idx);
+            state = self.transitions[state][idx];
+        }
+        self.accepting[state]
+    }
+}
+
+// Every character a Regex tree can actually branch on: literal characters, plus
+// the digits (both cases, for radixes above 10)/point/sign that a Remainder node
+// consumes in its own radix.
+fn collect_alphabet(r: &Regex, alphabet: &mut BTreeSet<char>) {
+    match r {
+        Regex::Empty | Regex::Epsilon => {}
+        Regex::Literal(c) => {
+            alphabet.insert(*c);
+        }
+        Regex::Concat(rxs) | Regex::Or(rxs) | Regex::And(rxs) => {
+            for rx in rxs {
+                collect_alphabet(rx, alphabet);
+            }
+        }
+        Regex::Not(inner) | Regex::Star(inner) => collect_alphabet(inner, alphabet),
+        Regex::Remainder { radix, .. } => {
+            for digit in 0..*radix {
+                if let Some(c) = std::char::from_digit(digit, *radix) {
+                    alphabet.insert(c);
+                    alphabet.insert(c.to_ascii_uppercase());
+                }
+            }
+            alphabet.insert('.');
+            alphabet.insert('+');
+            alphabet.insert('-');
+        }
+    }
+}
+
+// A character guaranteed not to be in `alphabet`, standing in for every character
+// the Regex tree doesn't distinguish.
+fn pick_wildcard(alphabet: &BTreeSet<char>) -> char {
+    ('\u{0}'..=char::MAX)
+        .find(|c| !alphabet.contains(c))
+        .expect("alphabet cannot cover all of Unicode")
+}
+
+// Smart constructors that flatten nested Or/And, sort and dedup their children,
+// drop Empty from Or and Epsilon from Concat, collapse Not(Not(r)) to r, and
+// absorb Empty/everything nodes - so two derivatives that differ only in these
+// ways canonicalize to the same state key. Equivalent regexes that differ in other
+// ways (e.g. Star(Star(r)) and Star(r)) still become distinct states.
+fn canonicalize(r: &Regex) -> Regex {
+    match r {
+        Regex::Or(rxs) => {
+            let mut children = Vec::new();
+            for rx in rxs {
+                match canonicalize(rx) {
+                    Regex::Or(inner) => children.extend(inner),
+                    Regex::Empty => {}
+                    other => children.push(other),
+                }
+            }
+            children.sort();
+            children.dedup();
+            if children.iter().any(is_everything) {
+                return everything();
+            }
+            match children.len() {
+                0 => Regex::Empty,
+                1 => children.into_iter().next().unwrap(),
+                _ => Regex::Or(children),
+            }
+        }
+        Regex::And(rxs) => {
+            let mut children = Vec::new();
+            for rx in rxs {
+                match canonicalize(rx) {
+                    Regex::And(inner) => children.extend(inner),
+                    other if is_everything(&other) => {}
+                    other => children.push(other),
+                }
+            }
+            children.sort();
+            children.dedup();
+            if children.iter().any(|rx| matches!(rx, Regex::Empty)) {
+                return Regex::Empty;
+            }
+            match children.len() {
+                0 => everything(),
+                1 => children.into_iter().next().unwrap(),
+                _ => Regex::And(children),
+            }
+        }
+        Regex::Concat(rxs) => {
+            let mut children = Vec::new();
+            for rx in rxs {
+                match canonicalize(rx) {
+                    Regex::Empty => return Regex::Empty,
+                    Regex::Epsilon => {}
+                    Regex::Concat(inner) => children.extend(inner),
+                    other => children.push(other),
+                }
+            }
+            match children.len() {
+                0 => Regex::Epsilon,
+                1 => children.into_iter().next().unwrap(),
+                _ => Regex::Concat(children),
+            }
+        }
+        Regex::Not(inner) => match canonicalize(inner) {
+            Regex::Not(doubled) => *doubled,
+            other => Regex::Not(Box::new(other)),
+        },
+        Regex::Star(inner) => Regex::Star(Box::new(canonicalize(inner))),
+        other => other.clone(),
+    }
+}
+
+fn everything() -> Regex {
+    Regex::Not(Box::new(Regex::Empty))
+}
+
+fn is_everything(r: &Regex) -> bool {
+    matches!(r, Regex::Not(inner) if matches!(**inner, Regex::Empty))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dfa_matches_like_regex() {
+        let regex = Regex::Concat(vec![
+            Regex::Literal('a'),
+            Regex::Star(Box::new(Regex::Literal('b'))),
+        ]);
+        let dfa = Dfa::from_regex(&regex);
+
+        assert_eq!(dfa.matches("a"), true);
+        assert_eq!(dfa.matches("ab"), true);
+        assert_eq!(dfa.matches("abb"), true);
+        assert_eq!(dfa.matches("aba"), false);
+        assert_eq!(dfa.matches("b"), false);
+    }
+
+    #[test]
+    fn test_dfa_remainder() {
+        for divisor in 1..=27 {
+            for remainder in 0..divisor {
+                let regex = Regex::remainder(divisor, remainder);
+                let dfa = Dfa::from_regex(&regex);
+                for i in 0..200 {
+                    let s = i.to_string();
+                    assert_eq!(
+                        dfa.matches(&s),
+                        i % divisor == remainder,
+                        "{} % {} == {}",
+                        s,
+                        divisor,
+                        remainder
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dfa_not_everything() {
+        let regex = Regex::Not(Box::new(Regex::Empty));
+        let dfa = Dfa::from_regex(&regex);
+
+        assert_eq!(regex.matches("hello"), true);
+        assert_eq!(dfa.matches("hello"), true);
+    }
+
+    #[test]
+    fn test_dfa_not_literal() {
+        let regex = Regex::Not(Box::new(Regex::Literal('a')));
+        let dfa = Dfa::from_regex(&regex);
+
+        for s in ["a", "b", "hello", ""] {
+            assert_eq!(dfa.matches(s), regex.matches(s), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn test_dfa_remainder_radix_hex_uppercase() {
+        let regex = Regex::remainder_radix(16, "8", "0").unwrap();
+        let dfa = Dfa::from_regex(&regex);
+
+        assert_eq!(dfa.matches("F8"), true);
+        assert_eq!(dfa.matches("FF"), false);
+        assert_eq!(dfa.matches("f8"), true);
+    }
+
+    #[test]
+    fn test_dfa_is_reused_across_inputs() {
+        // The same Dfa value is matched against many inputs, unlike `Regex::matches`
+        // which rebuilds the tree on every character of every call.
+        let dfa = Dfa::from_regex(&Regex::remainder(5, 0));
+        let inputs: Vec<String> = (0..50).map(|i| i.to_string()).collect();
+        for s in &inputs {
+            assert_eq!(dfa.matches(s), s.parse::<u32>().unwrap() % 5 == 0);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod ast;
+pub mod dfa;
+pub mod normalize;
@@ -0,0 +1,121 @@
+// Rewrites inputs like "1.5e3", "1_000", or "2,500.00" into the plain fixed-point
+// form the derivative engine in `ast` already understands, before `matches`/`Dfa`
+// ever sees them.
+
+#[derive(Debug, Clone)]
+pub struct NormalizeOptions {
+    // Characters stripped from the input before any other processing, e.g. the
+    // `,` in "2,500.00" or the `_` in "1_000".
+    pub grouping_separators: Vec<char>,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        NormalizeOptions {
+            grouping_separators: vec![',', '_'],
+        }
+    }
+}
+
+pub fn normalize(s: &str, options: &NormalizeOptions) -> String {
+    let ungrouped: String = s
+        .chars()
+        .filter(|c| !options.grouping_separators.contains(c))
+        .collect();
+    expand_exponent(&ungrouped)
+}
+
+fn expand_exponent(s: &str) -> String {
+    let exponent_at = match s.find(|c| c == 'e' || c == 'E') {
+        Some(pos) => pos,
+        None => return s.to_string(),
+    };
+
+    let (mantissa, exponent_part) = s.split_at(exponent_at);
+    let exponent: i32 = match exponent_part[1..].parse() {
+        Ok(e) => e,
+        Err(_) => return s.to_string(),
+    };
+
+    shift_point(mantissa, exponent)
+}
+
+// Shifts the radix point in `mantissa` by `exponent` places, inserting zeros as
+// needed, mirroring how a scaled-integer decimal type absorbs an exponent into
+// its scale instead of keeping it around symbolically.
+fn shift_point(mantissa: &str, exponent: i32) -> String {
+    let (sign, digits_part) = match mantissa.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => match mantissa.strip_prefix('+') {
+            Some(rest) => ("+", rest),
+            None => ("", mantissa),
+        },
+    };
+
+    let mut parts = digits_part.splitn(2, '.');
+    let integer_part = parts.next().unwrap_or("");
+    let fractional_part = parts.next().unwrap_or("");
+
+    let mut digits: Vec<char> = integer_part.chars().chain(fractional_part.chars()).collect();
+    let mut point = integer_part.len() as i32 + exponent;
+
+    while point > digits.len() as i32 {
+        digits.push('0');
+    }
+    while point < 0 {
+        digits.insert(0, '0');
+        point += 1;
+    }
+
+    let (int_digits, frac_digits) = digits.split_at(point as usize);
+
+    let mut result = String::from(sign);
+    if int_digits.is_empty() {
+        result.push('0');
+    } else {
+        result.extend(int_digits);
+    }
+    if !frac_digits.is_empty() {
+        result.push('.');
+        result.extend(frac_digits);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ast::Regex;
+
+    #[test]
+    fn test_normalize_scientific_notation() {
+        let options = NormalizeOptions::default();
+        assert_eq!(normalize("1.5e3", &options), "1500");
+        assert_eq!(normalize("1.5e-3", &options), "0.0015");
+        assert_eq!(normalize("2.5E1", &options), "25");
+        assert_eq!(normalize("15e2", &options), "1500");
+    }
+
+    #[test]
+    fn test_normalize_grouping_separators() {
+        let options = NormalizeOptions::default();
+        assert_eq!(normalize("1_000", &options), "1000");
+        assert_eq!(normalize("2,500.00", &options), "2500.00");
+    }
+
+    #[test]
+    fn test_matches_normalized_unchanged_for_raw_matches() {
+        // `matches` itself still can't read "1_000" - callers opt in explicitly.
+        let regex = Regex::remainder(5, 0);
+        assert_eq!(regex.matches("1_000"), false);
+        assert_eq!(
+            regex.matches_normalized("1_000", &NormalizeOptions::default()),
+            true
+        );
+        assert_eq!(
+            regex.matches_normalized("2.5e2", &NormalizeOptions::default()),
+            true
+        );
+    }
+}